@@ -0,0 +1,84 @@
+//! A tiny ANSI escape-sequence helper for `print_world`.
+//!
+//! Tracks the currently active style and only emits an escape sequence
+//! when an attribute actually changes, so coloring a whole grid doesn't
+//! spam the terminal with redundant codes.
+
+use std::io::IsTerminal;
+
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub struct Style {
+    pub fg: Option<u8>,
+    pub bg: Option<u8>,
+    pub bold: bool,
+}
+
+pub struct AnsiWriter {
+    current: Style,
+    enabled: bool,
+}
+
+impl AnsiWriter {
+    /// Creates a writer that emits real escape codes only when `stdout`
+    /// is a TTY; otherwise every call degrades to plain text. Passing
+    /// `force_plain` (e.g. from a `--no-color` flag) disables color
+    /// even on a TTY.
+    pub fn new(force_plain: bool) -> Self {
+        AnsiWriter {
+            current: Style::default(),
+            enabled: !force_plain && std::io::stdout().is_terminal(),
+        }
+    }
+
+    /// Switches to `style`, emitting only the attributes that changed
+    /// since the last call, and returns the escape sequence to print.
+    pub fn set(&mut self, style: Style) -> String {
+        if !self.enabled || style == self.current {
+            return String::new();
+        }
+
+        let mut codes: Vec<String> = Vec::new();
+        if style.bold != self.current.bold {
+            codes.push(if style.bold { "1".into() } else { "22".into() });
+        }
+        if style.fg != self.current.fg {
+            codes.push(match style.fg {
+                Some(c) => format!("38;5;{}", c),
+                None => "39".into(),
+            });
+        }
+        if style.bg != self.current.bg {
+            codes.push(match style.bg {
+                Some(c) => format!("48;5;{}", c),
+                None => "49".into(),
+            });
+        }
+
+        self.current = style;
+        if codes.is_empty() {
+            String::new()
+        } else {
+            format!("\x1b[{}m", codes.join(";"))
+        }
+    }
+
+    /// Resets all attributes, e.g. at the end of a line.
+    pub fn reset(&mut self) -> String {
+        if !self.enabled || self.current == Style::default() {
+            return String::new();
+        }
+        self.current = Style::default();
+        "\x1b[0m".to_string()
+    }
+}
+
+/// Picks a stable 256-color palette index for a player ID, so the same
+/// player keeps the same color for the whole match.
+pub fn color_for_player(id: usize) -> u8 {
+    // skip 0..=16 (the plain named colors, poor contrast on some
+    // terminals, and the cube's black) and the white and grayscale ramp
+    // at the end (231..=255)
+    const PALETTE_START: u8 = 17;
+    const PALETTE_LEN: u8 = 214;
+    PALETTE_START + (id as u8 % PALETTE_LEN)
+}
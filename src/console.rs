@@ -0,0 +1,43 @@
+//! Commands the user can type into stdin while the bot is running.
+
+/// A command entered interactively while a match is in progress.
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    /// `say <text>` - send a chat message
+    Say(String),
+    /// `move <dir>` - override the bot's move for the current tick
+    Move(String),
+    /// `debug on|off` - toggle debug logging at runtime
+    Debug(bool),
+    /// `quit` - leave the match and exit cleanly
+    Quit,
+}
+
+/// Parses one line typed at the console. Returns `Err` for blank input
+/// (with an empty message) or anything that isn't a recognized command
+/// (with a reason suitable for echoing back to the user).
+pub fn parse(line: &str) -> Result<Command, String> {
+    let line = line.trim();
+    let mut parts = line.splitn(2, ' ');
+    let cmd = parts.next().unwrap_or("");
+    let rest = parts.next().unwrap_or("").trim();
+
+    match cmd {
+        "say" if !rest.is_empty() => Ok(Command::Say(rest.to_string())),
+        "say" => Err("Usage: say <text>".to_string()),
+
+        "move" if ["right", "left", "up", "down"].contains(&rest) => {
+            Ok(Command::Move(rest.to_string()))
+        }
+        "move" => Err("Usage: move <right|left|up|down>".to_string()),
+
+        "debug" if rest == "on" => Ok(Command::Debug(true)),
+        "debug" if rest == "off" => Ok(Command::Debug(false)),
+        "debug" => Err("Usage: debug <on|off>".to_string()),
+
+        "quit" => Ok(Command::Quit),
+
+        "" => Err(String::new()),
+        other => Err(format!("Unknown command: \"{}\"", other)),
+    }
+}
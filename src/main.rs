@@ -1,21 +1,36 @@
+use std::collections::HashMap;
 use std::fs::read_to_string;
-use std::io::{BufRead, BufReader, Write};
 use std::iter::Iterator;
-use std::net::TcpStream;
-use std::str::FromStr;
-use std::sync::mpsc::{channel, TryRecvError};
-use std::thread;
-use std::time::Duration;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::tcp::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::TcpStream;
+
+mod ansi;
+mod console;
+mod metrics;
+mod protocol;
+mod server;
+mod session;
+mod strategy;
+
+use metrics::Metrics;
+use protocol::Message;
+use session::{GameOutcome, MatchResult, Session};
 
 const SERVER_ADDR: &str = "151.216.74.213:4000";
+const LOCAL_SERVER_ADDR: &str = "127.0.0.1:4000";
+const DEFAULT_METRICS_ADDR: &str = "127.0.0.1:9000";
 const USERNAME: &str = "MASTER CONTROL PROGRAM";
-const DEBUG: bool = true;
+const DEBUG_DEFAULT: bool = true;
 
-type PlayerID = usize;
+pub(crate) type PlayerID = usize;
 type Coord = usize;
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum Direction {
     WPos,
     WNeg,
     HPos,
@@ -23,8 +38,8 @@ enum Direction {
 }
 
 #[derive(Clone)]
-struct Cell {
-    claimed_by: Option<PlayerID>,
+pub(crate) struct Cell {
+    pub(crate) claimed_by: Option<PlayerID>,
 }
 
 impl Cell {
@@ -32,15 +47,15 @@ impl Cell {
         Cell { claimed_by: None }
     }
 
-    fn claimed(&self) -> bool {
+    pub(crate) fn claimed(&self) -> bool {
         self.claimed_by.is_some()
     }
 }
 
 struct Game {
     username: String,
-    reader: BufReader<TcpStream>,
-    writer: TcpStream,
+    reader: BufReader<OwnedReadHalf>,
+    writer: OwnedWriteHalf,
     read_buf: String,
     // our ID - None means we don't know our ID yet
     me: Option<PlayerID>,
@@ -48,36 +63,56 @@ struct Game {
     others: Vec<Option<String>>,
     world: Vec<Vec<Cell>>, // semantic: [width_offset][height_offset]
     pos: (usize, usize),
+    // most recently reported position of every player still in the match,
+    // i.e. their current head - used for the Voronoi territory strategy
+    heads: HashMap<PlayerID, (Coord, Coord)>,
+    // ticks seen since the current match started - reset by `reset()`
+    match_ticks: u32,
+    no_color: bool,
+    metrics: Option<Arc<Metrics>>,
+    debug: bool,
+    session: Option<Session>,
 }
 
 impl Game {
-    fn new(username: &str) -> Self {
+    async fn new(
+        username: &str,
+        no_color: bool,
+        metrics: Option<Arc<Metrics>>,
+        session: Option<Session>,
+    ) -> Self {
         // connect to server
         println!("Connecting to server: {}", SERVER_ADDR);
-        let addr = SERVER_ADDR.parse()
-            .unwrap_or_else(|_| panic!("Cannot parse server address: {}", SERVER_ADDR));
-        let stream = TcpStream::connect_timeout(&addr, Duration::new(10, 0))
+        let stream = tokio::time::timeout(Duration::new(10, 0), TcpStream::connect(SERVER_ADDR))
+            .await
+            .expect("Timed out connecting to server")
             .expect("Cannot connect to server");
-        let r = BufReader::new(stream.try_clone().expect("Cannot clone TCPStream"));
+        let (r, w) = stream.into_split();
 
         // return game object
         Game {
             username: String::from(username),
-            reader: r,
-            writer: stream,
+            reader: BufReader::new(r),
+            writer: w,
             read_buf: String::with_capacity(256),
             me: None,
             others: Vec::new(),
             // for performance sake, assume a big world from the get-go
             world: Vec::with_capacity(50^2),
             pos: (0,0), // we assume our position will be updated soon
+            heads: HashMap::new(),
+            match_ticks: 0,
+            no_color,
+            metrics,
+            debug: DEBUG_DEFAULT,
+            session,
         }
     }
 
-    fn join(&mut self, pas: &str) {
+    async fn join(&mut self, pas: &str) {
         println!("Sending JOIN to join next game");
         let usr = self.username.clone();
-        self.send("join", Some(&[usr.as_str(), pas]));
+        self.send_raw(Message::encode_join(&usr, pas)).await;
     }
 
     fn reset(&mut self, width: Coord, height: Coord, me: PlayerID) {
@@ -85,31 +120,33 @@ impl Game {
         self.me = Some(me);
         self.others.clear();
         self.world = vec![vec![Cell::new(); height]; width];
+        self.heads.clear();
+        self.match_ticks = 0;
     }
 
-    fn send(&mut self, msg_type: &str, msg_args: Option<&[&str]>) {
-        let msg = match msg_args {
-            None => msg_type.into(),
-            Some(a) => format!("{}|{}\n", msg_type, a.join("|")),
-        };
-        if DEBUG {
+    async fn send_raw(&mut self, msg: String) {
+        if self.debug {
             println!("Sending msg: {}", msg.trim());
         }
         self.writer
             .write_all(msg.as_bytes())
+            .await
             .unwrap_or_else(|_| panic!("Failed sending message to server: {}", msg));
-        self.writer.flush().expect("Failed flushing");
+        self.writer.flush().await.expect("Failed flushing");
     }
 
-    fn receive(&mut self) -> &String {
+    /// Reads one line from the server. Returns the number of bytes read
+    /// (`0` means the connection was closed).
+    async fn receive(&mut self) -> usize {
         self.read_buf.clear();
-        self.reader
+        let n = self.reader
             .read_line(&mut self.read_buf)
+            .await
             .expect("Cannot read line from server");
-        if !self.read_buf.is_empty() && DEBUG {
+        if !self.read_buf.is_empty() && self.debug {
             println!("Received message: {}", self.read_buf.trim());
         }
-        &self.read_buf
+        n
     }
 
     fn add_player(&mut self, id: PlayerID, name: String) {
@@ -126,6 +163,7 @@ impl Game {
                 self.others[id] = Some(name);
             }
         }
+        self.update_opponent_gauge();
     }
 
     fn remove_player(&mut self, id: PlayerID) {
@@ -142,6 +180,15 @@ impl Game {
                 }
             }
         }
+        self.heads.remove(&id);
+        self.update_opponent_gauge();
+    }
+
+    fn update_opponent_gauge(&self) {
+        if let Some(metrics) = &self.metrics {
+            let count = self.others.iter().filter(|o| o.is_some()).count();
+            metrics.opponent_count.set(count as i64);
+        }
     }
 
     fn get_player_name(&self, player_id: PlayerID) -> Option<&str> {
@@ -156,26 +203,49 @@ impl Game {
     fn occupy(&mut self, player_id: PlayerID, w: Coord, h: Coord) {
         // we assume that the field is not yet claimed by anyone
         self.world[w][h] = Cell { claimed_by: Some(player_id) };
+        self.heads.insert(player_id, (w, h));
+
+        if let Some(metrics) = &self.metrics {
+            if Some(player_id) == self.me {
+                metrics.cells_claimed.inc();
+            }
+            // our head is itself claimed, so flood from it as if it were
+            // free and leave the head out of the count
+            let mut world = self.world.clone();
+            world[self.pos.0][self.pos.1].claimed_by = None;
+            let free = strategy::flood_fill_area(&world, self.pos).saturating_sub(1);
+            metrics.free_space.set(free as i64);
+        }
     }
 
-    fn say(&mut self, msg: &str) {
-        self.send("chat", Some(&[msg]));
+    async fn say(&mut self, msg: &str) {
+        self.send_raw(Message::encode_chat(msg)).await;
     }
 
     fn print_world(&self) {
         let expanse_w = self.world.len();
         println!("World (expanse_w == {}):", expanse_w);
+        let mut ansi = ansi::AnsiWriter::new(self.no_color);
         // we have to iterate backwards (using `rev()`) for correct orientation
         for w in (0..expanse_w).rev() {
             for h in (0..self.world[w].len()).rev() {
                 let cell = &self.world[w][h];
-                if cell.claimed() {
-                    print!("{:02}", cell.claimed_by.unwrap());
-                } else {
-                    print!("--");
+                let is_head = self.me.is_some() && self.pos == (w, h);
+                match cell.claimed_by {
+                    Some(id) => {
+                        let style = ansi::Style {
+                            fg: Some(0),
+                            bg: Some(ansi::color_for_player(id)),
+                            bold: is_head,
+                        };
+                        print!("{}{:02}", ansi.set(style), id);
+                    }
+                    None => {
+                        print!("{}--", ansi.set(ansi::Style::default()));
+                    }
                 }
             }
-            println!()
+            println!("{}", ansi.reset());
         }
         if self.me.is_some() {
             println!("(My ID was {})", self.me.unwrap());
@@ -185,31 +255,23 @@ impl Game {
     }
 }
 
-fn parse_msg_arg<T: FromStr>(arg: &str, err_msg: &str) -> T {
-    let arg = arg.trim();
-    arg.parse()
-        .unwrap_or_else(|_| panic!("{}: \"{}\"", err_msg, arg))
+/// Returns the value following `flag` in the process's argument list,
+/// e.g. `arg_value("--metrics-addr")` for `... --metrics-addr 1.2.3.4:9000 ...`.
+fn arg_value(flag: &str) -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter().position(|a| a == flag).and_then(|i| args.get(i + 1)).cloned()
 }
 
-fn beam(game: &Game, dir: Direction) -> usize {
-    // we assume that the world is the same size in all dimensions
-    let expanse = game.world.len();
-    let mut beam_len = 0;
-    for offset in 0..expanse-1 {
-        if !match dir {
-            // `+ expanse` in *Neg cases because we cannot underflow before the modulo op
-            Direction::WPos => game.world[(game.pos.0 + offset) % expanse][game.pos.1].claimed(),
-            Direction::WNeg => game.world[(game.pos.0 + expanse - offset) % expanse][game.pos.1].claimed(),
-            Direction::HPos => game.world[game.pos.0][(game.pos.1 + offset) % expanse].claimed(),
-            Direction::HNeg => game.world[game.pos.0][(game.pos.1 + expanse - offset) % expanse].claimed(),
-        } {
-            beam_len += 1;
-        }
+#[tokio::main]
+async fn main() {
+    // `--serve` runs the local reference server on LOCAL_SERVER_ADDR
+    // instead of the bot. The bot itself always connects to SERVER_ADDR;
+    // it has no option to connect to the local server instead.
+    if std::env::args().any(|a| a == "--serve") {
+        server::run(LOCAL_SERVER_ADDR);
+        return;
     }
-    beam_len
-}
 
-fn main() {
     // read username from file
     let un_file = "./username";
     let username = read_to_string(un_file).unwrap_or(String::from(USERNAME));
@@ -222,161 +284,278 @@ fn main() {
         .unwrap_or_else(|_| panic!("Cannot read password from file: \"{}\"", pw_file));
     let password = password.trim();
 
+    // set up metrics, if requested
+    let metrics = if std::env::args().any(|a| a == "--metrics") {
+        let metrics = Arc::new(Metrics::new());
+        let addr = arg_value("--metrics-addr").unwrap_or(String::from(DEFAULT_METRICS_ADDR));
+        metrics::serve(metrics.clone(), &addr);
+        Some(metrics)
+    } else {
+        None
+    };
+
+    // set up the session layer, if requested, for multi-game stats and auto-rejoin
+    let session = if std::env::args().any(|a| a == "--session") {
+        let match_cap = arg_value("--match-cap").and_then(|n| n.parse().ok());
+        Some(Session::new(arg_value("--stats-file"), match_cap))
+    } else {
+        None
+    };
+
     // connect to server
-    let mut game = Game::new(username);
+    let no_color = std::env::args().any(|a| a == "--no-color");
+    let mut game = Game::new(username, no_color, metrics, session).await;
 
     // join next game
-    game.join(password);
+    game.join(password).await;
 
     // count empty messages
     let mut empty_msgs = 0;
 
-    // spawn canary thread
-    let (canary_tx, canary_rx) = channel();
-    thread::spawn(move || {
-        // block until user hits enter
-        let mut buf = String::new();
-        let _ = std::io::stdin().read_line(&mut buf);
-        println!("Canary thread got input line. Telling main thread to exit.");
-        let _ = canary_tx.send(());
-    });
+    // the console lets the user chat, override our move for one tick,
+    // toggle debug logging, or quit - all while messages keep streaming in
+    let mut console_lines = BufReader::new(tokio::io::stdin()).lines();
+    let mut move_override: Option<String> = None;
+    // stdin is closed under any headless deployment (systemd, Docker
+    // without -it, nohup ... </dev/null): once next_line() resolves to
+    // Ok(None) once, it resolves to Ok(None) on every subsequent poll, so
+    // without this guard the select! below livelocks on the console arm
+    // and starves game.receive() forever.
+    let mut console_open = true;
 
     // read loop
     loop {
+        tokio::select! {
+            // a line typed at the console
+            console_line = console_lines.next_line(), if console_open => {
+                let line = match console_line {
+                    Ok(Some(line)) => line,
+                    // stdin closed - stop polling the console and keep
+                    // processing server messages
+                    Ok(None) => {
+                        console_open = false;
+                        continue;
+                    }
+                    Err(e) => {
+                        println!("Cannot read console input: {}", e);
+                        continue;
+                    }
+                };
 
-        // check whether canary thread told us to exit
-        match canary_rx.try_recv() {
-            Ok(_) => {
-                println!("Canary thread got input. Main thread exiting.");
-                return;
-            },
-            Err(TryRecvError::Disconnected) => panic!("Canary thread channel got disconnected"),
-            Err(TryRecvError::Empty) => { /* we live another tick */ },
-        };
-
-        // read from server
-        let msg = game.receive();
-
-        // ignore empty messages
-        if msg.is_empty() {
-            println!("Got empty message. Ignoring. Got {} empty messages so far btw.",
-                empty_msgs);
-            empty_msgs += 1;
-            continue;
-        }
+                match console::parse(&line) {
+                    Ok(console::Command::Say(text)) => game.say(&text).await,
+                    Ok(console::Command::Move(dir)) => {
+                        println!("Overriding next move with: {}", dir);
+                        move_override = Some(dir);
+                    }
+                    Ok(console::Command::Debug(on)) => {
+                        game.debug = on;
+                        println!("Debug logging is now {}", if on { "on" } else { "off" });
+                    }
+                    Ok(console::Command::Quit) => {
+                        println!("Leaving the match.");
+                        game.send_raw(Message::encode("leave", &[])).await;
+                        if let Some(session) = &game.session {
+                            println!("{}", session.summary());
+                        }
+                        return;
+                    }
+                    Err(msg) if !msg.is_empty() => println!("{}", msg),
+                    Err(_) => { /* blank line - ignore */ }
+                }
+            }
 
-        // parse message
-        let msg_args: Vec<&str> = msg.split('|').collect();
-        let msg_type: &str = msg_args[0].trim();
+            // a line from the server
+            n = game.receive() => {
+                // ignore empty lines
+                if n == 0 || game.read_buf.trim().is_empty() {
+                    println!("Got empty message. Ignoring. Got {} empty messages so far btw.",
+                        empty_msgs);
+                    empty_msgs += 1;
+                    continue;
+                }
 
-        // decide action
-        match msg_type {
-            // error - bail out
-            "error" => return,
+                // parse message
+                let msg = match protocol::parse_line(&game.read_buf) {
+                    Ok(msg) => msg,
+                    Err(e) => {
+                        println!("Ignoring unparseable message: {}", e);
+                        continue;
+                    }
+                };
 
-            // MOTD - print
-            "motd" => {
-                println!("MOTD: {}", msg_args[1].trim());
-            }
+                // decide action
+                match msg {
+                    // error - bail out
+                    Message::Error => return,
 
-            // new game - reset game state
-            "game" => {
-                let width = parse_msg_arg(msg_args[1], "Cannot parse map width");
-                let height = parse_msg_arg(msg_args[2], "Cannot parse map height");
-                let id = parse_msg_arg(msg_args[3], "Cannot parse ID");
-                println!("\nNew game has started! The world has a width of {} and a height of {}",
-                    width, height);
-                game.reset(width, height, id);
-                game.say("You shouldn't have come back, Flynn.");
-            }
+                    // MOTD - print
+                    Message::Motd(text) => {
+                        println!("MOTD: {}", text);
+                    }
 
-            // tick - make a move
-            "tick" => {
-                if DEBUG {
-                    game.print_world();
-                }
+                    // new game - reset game state
+                    Message::Game { w, h, id } => {
+                        println!("\nNew game has started! The world has a width of {} and a height of {}",
+                            w, h);
+                        game.reset(w, h, id);
+                        game.say("You shouldn't have come back, Flynn.").await;
+                    }
 
-                // simple strategy - beam into all four directions
-                let mut best_dir = Direction::WPos;
-                let mut longest_beam = 0;
-                for dir in [Direction::WPos, Direction::WNeg, Direction::HPos, Direction::HNeg] {
-                    let beam = beam(&game, dir);
-                    if beam > longest_beam {
-                        best_dir = dir;
-                        longest_beam = beam;
+                    // tick - make a move
+                    Message::Tick => {
+                        game.match_ticks += 1;
+
+                        if game.debug {
+                            game.print_world();
+                        }
+
+                        let decision_start = Instant::now();
+
+                        // an interactive `move` command overrides the bot for this tick
+                        let direction_name = if let Some(dir) = move_override.take() {
+                            println!("Using console override for this tick: {}", dir);
+                            dir
+                        } else {
+                            let opponents: Vec<(PlayerID, (usize, usize))> = game.heads.iter()
+                                .filter(|(id, _)| Some(**id) != game.me)
+                                .map(|(id, pos)| (*id, *pos))
+                                .collect();
+                            let body_length = game.world.iter().flatten()
+                                .filter(|c| c.claimed_by == game.me)
+                                .count();
+
+                            let best_dir = strategy::choose_move(
+                                &game.world, game.me.unwrap(), game.pos, &opponents, body_length,
+                            ).unwrap_or(Direction::WPos); // no legal move found - we're probably dead already
+                            println!("Best direction to move into is: {:?}", best_dir);
+                            match best_dir {
+                                Direction::WPos => "right",
+                                Direction::WNeg => "left",
+                                Direction::HPos => "up",
+                                Direction::HNeg => "down",
+                            }.to_string()
+                        };
+
+                        // stop the clock before the network write so the metric
+                        // measures strategy computation, not send/flush latency
+                        let decision_elapsed = decision_start.elapsed();
+
+                        println!("Moving {}", direction_name);
+                        game.send_raw(Message::encode_move(&direction_name)).await;
+
+                        if let Some(metrics) = &game.metrics {
+                            metrics.ticks_processed.inc();
+                            metrics.decision_latency.observe(decision_elapsed.as_secs_f64());
+                        }
                     }
-                }
-                println!("Best direction to move into is: {:?}", best_dir);
-
-                // move into best direction
-                let direction_name = match best_dir {
-                    Direction::WPos => "right",
-                    Direction::WNeg => "left",
-                    Direction::HPos => "up",
-                    Direction::HNeg => "down",
-                };
-                println!("Moving {}", direction_name);
-                game.send("move", Some(&[direction_name]));
-            }
 
-            // register players
-            "player" => {
-                let id = parse_msg_arg(msg_args[1], "Cannot parse to number");
-                let name = String::from(msg_args[2].trim());
-                println!("Registering player {} \"{}\"", id, name);
-                game.add_player(id, name);
-            }
+                    // register players
+                    Message::Player { id, name } => {
+                        println!("Registering player {} \"{}\"", id, name);
+                        game.add_player(id, name);
+                    }
 
-            // update claimed cells in the world
-            "pos" => {
+                    // update claimed cells in the world
+                    Message::Pos { id: player_id, x, y } => {
 
-                // claim cell
-                let player_id = parse_msg_arg(msg_args[1], "Cannot parse player ID");
-                let x = parse_msg_arg(msg_args[2], "Cannot parse position (x)");
-                let y = parse_msg_arg(msg_args[3], "Cannot parse position (y)");
-                game.occupy(player_id, x, y);
+                        // claim cell
+                        game.occupy(player_id, x, y);
 
-                // if the position relates to us, update our position
-                if game.me == Some(player_id) {
-                    if DEBUG {
-                        println!("We're currently at ({},{}).", x, y);
+                        // if the position relates to us, update our position
+                        if game.me == Some(player_id) {
+                            if game.debug {
+                                println!("We're currently at ({},{}).", x, y);
+                            }
+                            game.pos = (x,y);
+                        }
                     }
-                    game.pos = (x,y);
-                }
-            }
 
-            // log chat messages
-            "chat" => {
-                let id: PlayerID = parse_msg_arg(msg_args[1], "Cannot parse player ID");
-                let msg = String::from(msg_args[2].trim());
-                let name: String = match game.get_player_name(id) {
-                    None => String::from("UNKNOWN"),
-                    Some(n) => format!("\"{}\"", n),
-                };
-                println!("Player {} ({}) said: \"{}\"", id, name, msg);
-            }
+                    // log chat messages
+                    Message::Chat { id, text } => {
+                        let name: String = match game.get_player_name(id) {
+                            None => String::from("UNKNOWN"),
+                            Some(n) => format!("\"{}\"", n),
+                        };
+                        println!("Player {} ({}) said: \"{}\"", id, name, text);
+                        if let Some(metrics) = &game.metrics {
+                            metrics.chat_events.inc();
+                        }
+                    }
 
-            "die" => {
-                let id: PlayerID = parse_msg_arg(msg_args[1], "Cannot parse player ID");
-                let name: String = match game.get_player_name(id) {
-                    None => String::from("UNKNOWN"),
-                    Some(n) => format!("\"{}\"", n),
-                };
-                println!("Player {} (\"{}\") died. Removing their blocked cells.", id, name);
-                game.remove_player(id);
-            }
+                    Message::Die { id } => {
+                        let name: String = match game.get_player_name(id) {
+                            None => String::from("UNKNOWN"),
+                            Some(n) => format!("\"{}\"", n),
+                        };
+                        println!("Player {} (\"{}\") died. Removing their blocked cells.", id, name);
+                        game.remove_player(id);
+                        if let Some(metrics) = &game.metrics {
+                            metrics.die_events.inc();
+                        }
+                    }
 
-            "lose" => {
-                let won: u32 = parse_msg_arg(msg_args[1], "Cannot parse amount of wins");
-                let lost: u32 = parse_msg_arg(msg_args[2], "Cannot parse amount of losses");
-                println!("Lost. Won {} times, lost {} times.", won, lost);
-                game.print_world();
-            }
+                    Message::Lose { won, lost } => {
+                        println!("Lost. Won {} times, lost {} times.", won, lost);
+                        game.print_world();
+                        if let Some(metrics) = &game.metrics {
+                            metrics.lose_events.inc();
+                        }
+                        if !finish_match(&mut game, MatchResult::Lost, password).await {
+                            return;
+                        }
+                    }
 
-            "win" => println!("THE VICTORY IS OURS!"),
+                    Message::Win => {
+                        println!("THE VICTORY IS OURS!");
+                        if let Some(metrics) = &game.metrics {
+                            metrics.win_events.inc();
+                        }
+                        if !finish_match(&mut game, MatchResult::Won, password).await {
+                            return;
+                        }
+                    }
+                };
+            }
+        }
+    }
+}
 
-            // NOP messages
-            _ => {}
-        };
+/// Records the just-finished match's outcome and, unless the configured
+/// match cap has been reached, sends `join` to queue for the next one.
+/// Returns `false` when the bot should exit instead of rejoining.
+async fn finish_match(game: &mut Game, result: MatchResult, password: &str) -> bool {
+    let claimed_cells = game.world.iter().flatten()
+        .filter(|c| c.claimed_by == game.me)
+        .count();
+    let surviving_opponents = game.others.iter().filter(|o| o.is_some()).count();
+    let timestamp_secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+
+    let should_rejoin = match &mut game.session {
+        None => true,
+        Some(session) => {
+            session.record(GameOutcome {
+                result,
+                claimed_cells,
+                surviving_opponents,
+                ticks: game.match_ticks,
+                timestamp_secs,
+            });
+            session.should_rejoin()
+        }
+    };
+
+    if should_rejoin {
+        game.join(password).await;
+        true
+    } else {
+        println!("Match cap reached.");
+        if let Some(session) = &game.session {
+            println!("{}", session.summary());
+        }
+        false
     }
 }
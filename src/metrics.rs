@@ -0,0 +1,123 @@
+//! Prometheus instrumentation for the bot, so a match can be scraped
+//! and graphed over time instead of read off debug prints.
+
+use std::io::{Read, Write};
+use std::net::TcpListener;
+use std::thread;
+
+use prometheus::{Encoder, Histogram, HistogramOpts, IntCounter, IntGauge, Registry, TextEncoder};
+
+pub struct Metrics {
+    registry: Registry,
+    pub ticks_processed: IntCounter,
+    pub cells_claimed: IntCounter,
+    pub chat_events: IntCounter,
+    pub die_events: IntCounter,
+    pub win_events: IntCounter,
+    pub lose_events: IntCounter,
+    pub free_space: IntGauge,
+    pub opponent_count: IntGauge,
+    pub decision_latency: Histogram,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let registry = Registry::new();
+
+        let ticks_processed = IntCounter::new("tronbot_ticks_processed_total", "Ticks processed")
+            .expect("Cannot create ticks_processed counter");
+        let cells_claimed = IntCounter::new("tronbot_cells_claimed_total", "Cells we have claimed")
+            .expect("Cannot create cells_claimed counter");
+        let chat_events = IntCounter::new("tronbot_chat_events_total", "Chat messages observed")
+            .expect("Cannot create chat_events counter");
+        let die_events = IntCounter::new("tronbot_die_events_total", "Die events observed")
+            .expect("Cannot create die_events counter");
+        let win_events = IntCounter::new("tronbot_win_events_total", "Matches won")
+            .expect("Cannot create win_events counter");
+        let lose_events = IntCounter::new("tronbot_lose_events_total", "Matches lost")
+            .expect("Cannot create lose_events counter");
+        let free_space = IntGauge::new("tronbot_reachable_free_space", "Currently reachable free space")
+            .expect("Cannot create free_space gauge");
+        let opponent_count = IntGauge::new("tronbot_opponent_count", "Currently known opponents")
+            .expect("Cannot create opponent_count gauge");
+        let decision_latency = Histogram::with_opts(HistogramOpts::new(
+            "tronbot_decision_latency_seconds",
+            "Time spent deciding a move per tick",
+        ))
+        .expect("Cannot create decision_latency histogram");
+
+        registry.register(Box::new(ticks_processed.clone())).expect("Cannot register ticks_processed");
+        registry.register(Box::new(cells_claimed.clone())).expect("Cannot register cells_claimed");
+        registry.register(Box::new(chat_events.clone())).expect("Cannot register chat_events");
+        registry.register(Box::new(die_events.clone())).expect("Cannot register die_events");
+        registry.register(Box::new(win_events.clone())).expect("Cannot register win_events");
+        registry.register(Box::new(lose_events.clone())).expect("Cannot register lose_events");
+        registry.register(Box::new(free_space.clone())).expect("Cannot register free_space");
+        registry.register(Box::new(opponent_count.clone())).expect("Cannot register opponent_count");
+        registry.register(Box::new(decision_latency.clone())).expect("Cannot register decision_latency");
+
+        Metrics {
+            registry,
+            ticks_processed,
+            cells_claimed,
+            chat_events,
+            die_events,
+            win_events,
+            lose_events,
+            free_space,
+            opponent_count,
+            decision_latency,
+        }
+    }
+
+    /// Renders the registry in the Prometheus text exposition format.
+    fn render(&self) -> Vec<u8> {
+        let encoder = TextEncoder::new();
+        let metric_families = self.registry.gather();
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .expect("Cannot encode metrics");
+        buffer
+    }
+}
+
+/// Spawns a thread that serves `GET /metrics` over plain HTTP on `addr`,
+/// handling one request per connection. Anything that isn't a GET for
+/// `/metrics` gets a 404.
+pub fn serve(metrics: std::sync::Arc<Metrics>, addr: &str) {
+    let listener = TcpListener::bind(addr).expect("Cannot bind metrics address");
+    println!("Serving Prometheus metrics on http://{}/metrics", addr);
+
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = match stream {
+                Ok(s) => s,
+                Err(_) => continue,
+            };
+
+            let mut buf = [0u8; 1024];
+            let n = match stream.read(&mut buf) {
+                Ok(n) => n,
+                Err(_) => continue,
+            };
+            let request = String::from_utf8_lossy(&buf[..n]);
+            let is_metrics_request = request.starts_with("GET /metrics");
+
+            let response = if is_metrics_request {
+                let body = metrics.render();
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\n\r\n",
+                    body.len()
+                );
+                let mut response = header.into_bytes();
+                response.extend_from_slice(&body);
+                response
+            } else {
+                b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\n\r\n".to_vec()
+            };
+
+            let _ = stream.write_all(&response);
+        }
+    });
+}
@@ -0,0 +1,211 @@
+//! The pipe-delimited wire protocol spoken with the game server.
+//!
+//! Lines look like `type|arg|arg\n`. This module turns that text into a
+//! typed `Message` (and back again) so the rest of the bot never has to
+//! touch `split('|')` or raw indices into `msg_args`.
+
+use std::fmt;
+
+use nom::branch::alt;
+use nom::bytes::complete::{tag, take_while};
+use nom::character::complete::{char, digit1};
+use nom::combinator::{eof, map, map_res, opt, value};
+use nom::sequence::{preceded, terminated};
+use nom::IResult;
+
+use crate::PlayerID;
+
+/// A single message received from (or sent to) the server.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Message {
+    Motd(String),
+    Game { w: usize, h: usize, id: PlayerID },
+    Tick,
+    Player { id: PlayerID, name: String },
+    Pos { id: PlayerID, x: usize, y: usize },
+    Chat { id: PlayerID, text: String },
+    Die { id: PlayerID },
+    Lose { won: u32, lost: u32 },
+    Win,
+    Error,
+}
+
+/// Something went wrong turning a line from the server into a `Message`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ProtocolError(String);
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "malformed protocol message: {}", self.0)
+    }
+}
+
+impl std::error::Error for ProtocolError {}
+
+/// Parses one line of the wire protocol into a `Message`.
+///
+/// Trailing whitespace (including the `\n`) is tolerated, and a blank
+/// line is reported as a `ProtocolError` rather than panicking, since
+/// the server is known to occasionally send empty keep-alive lines.
+pub fn parse_line(line: &str) -> Result<Message, ProtocolError> {
+    let trimmed = line.trim_end();
+    if trimmed.is_empty() {
+        return Err(ProtocolError("empty line".into()));
+    }
+    match message(trimmed) {
+        Ok((_, msg)) => Ok(msg),
+        Err(_) => Err(ProtocolError(trimmed.to_string())),
+    }
+}
+
+fn field(input: &str) -> IResult<&str, &str> {
+    preceded(char('|'), take_while(|c| c != '|'))(input)
+}
+
+fn number<T: std::str::FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(digit1, str::parse)(input)
+}
+
+fn numeric_field<T: std::str::FromStr>(input: &str) -> IResult<&str, T> {
+    preceded(char('|'), number)(input)
+}
+
+fn motd(input: &str) -> IResult<&str, Message> {
+    map(preceded(tag("motd"), field), |s: &str| Message::Motd(s.to_string()))(input)
+}
+
+fn game(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("game")(input)?;
+    let (input, w) = numeric_field(input)?;
+    let (input, h) = numeric_field(input)?;
+    let (input, id) = numeric_field(input)?;
+    Ok((input, Message::Game { w, h, id }))
+}
+
+fn tick(input: &str) -> IResult<&str, Message> {
+    value(Message::Tick, tag("tick"))(input)
+}
+
+fn player(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("player")(input)?;
+    let (input, id) = numeric_field(input)?;
+    let (input, name) = field(input)?;
+    Ok((input, Message::Player { id, name: name.to_string() }))
+}
+
+fn pos(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("pos")(input)?;
+    let (input, id) = numeric_field(input)?;
+    let (input, x) = numeric_field(input)?;
+    let (input, y) = numeric_field(input)?;
+    Ok((input, Message::Pos { id, x, y }))
+}
+
+fn chat(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("chat")(input)?;
+    let (input, id) = numeric_field(input)?;
+    let (input, text) = field(input)?;
+    Ok((input, Message::Chat { id, text: text.to_string() }))
+}
+
+fn die(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("die")(input)?;
+    let (input, id) = numeric_field(input)?;
+    Ok((input, Message::Die { id }))
+}
+
+fn lose(input: &str) -> IResult<&str, Message> {
+    let (input, _) = tag("lose")(input)?;
+    let (input, won) = numeric_field(input)?;
+    let (input, lost) = numeric_field(input)?;
+    Ok((input, Message::Lose { won, lost }))
+}
+
+fn win(input: &str) -> IResult<&str, Message> {
+    value(Message::Win, tag("win"))(input)
+}
+
+fn error(input: &str) -> IResult<&str, Message> {
+    value(Message::Error, tag("error"))(input)
+}
+
+fn message(input: &str) -> IResult<&str, Message> {
+    terminated(
+        alt((motd, game, tick, player, pos, chat, die, lose, win, error)),
+        terminated(opt(take_while(|c| c == '|' || c == ' ' || c == '\t')), eof),
+    )(input)
+}
+
+impl Message {
+    /// Serializes an outgoing command the way `Game::send` used to build
+    /// it by hand: `type|arg|arg\n`, or just `type\n` with no arguments.
+    pub fn encode(msg_type: &str, args: &[&str]) -> String {
+        if args.is_empty() {
+            format!("{}\n", msg_type)
+        } else {
+            format!("{}|{}\n", msg_type, args.join("|"))
+        }
+    }
+
+    /// Convenience encoder for the `move` command.
+    pub fn encode_move(direction: &str) -> String {
+        Self::encode("move", &[direction])
+    }
+
+    /// Convenience encoder for the `join` command.
+    pub fn encode_join(username: &str, password: &str) -> String {
+        Self::encode("join", &[username, password])
+    }
+
+    /// Convenience encoder for the `chat` command.
+    pub fn encode_chat(text: &str) -> String {
+        Self::encode("chat", &[text])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_one_line_per_variant() {
+        assert_eq!(parse_line("motd|hello there"), Ok(Message::Motd("hello there".to_string())));
+        assert_eq!(parse_line("game|20|20|3"), Ok(Message::Game { w: 20, h: 20, id: 3 }));
+        assert_eq!(parse_line("tick"), Ok(Message::Tick));
+        assert_eq!(parse_line("player|1|alice"), Ok(Message::Player { id: 1, name: "alice".to_string() }));
+        assert_eq!(parse_line("pos|1|4|5"), Ok(Message::Pos { id: 1, x: 4, y: 5 }));
+        assert_eq!(parse_line("chat|1|hi all"), Ok(Message::Chat { id: 1, text: "hi all".to_string() }));
+        assert_eq!(parse_line("die|1"), Ok(Message::Die { id: 1 }));
+        assert_eq!(parse_line("lose|2|3"), Ok(Message::Lose { won: 2, lost: 3 }));
+        assert_eq!(parse_line("win"), Ok(Message::Win));
+        assert_eq!(parse_line("error"), Ok(Message::Error));
+    }
+
+    #[test]
+    fn malformed_line_is_an_error_not_a_panic() {
+        assert!(parse_line("not-a-real-message").is_err());
+        assert!(parse_line("game|20|notanumber|3").is_err());
+        assert!(parse_line("").is_err());
+        assert!(parse_line("   ").is_err());
+    }
+
+    #[test]
+    fn tolerates_trailing_whitespace_and_pipes() {
+        assert_eq!(parse_line("tick\n"), Ok(Message::Tick));
+        assert_eq!(parse_line("tick\r\n"), Ok(Message::Tick));
+        assert_eq!(parse_line("die|1|\n"), Ok(Message::Die { id: 1 }));
+        assert_eq!(parse_line("win   \n"), Ok(Message::Win));
+    }
+
+    #[test]
+    fn encode_round_trips_through_parse_line() {
+        let line = Message::encode_move("right");
+        assert_eq!(line, "move|right\n");
+
+        let line = Message::encode("die", &["1"]);
+        assert_eq!(parse_line(&line), Ok(Message::Die { id: 1 }));
+
+        let line = Message::encode("chat", &["0", "hi all"]);
+        assert_eq!(parse_line(&line), Ok(Message::Chat { id: 0, text: "hi all".to_string() }));
+    }
+}
@@ -0,0 +1,387 @@
+//! A minimal reference server that speaks the same pipe-delimited
+//! protocol as the public match server, so the bot (and other bot
+//! instances) can be tested against a local, deterministic match
+//! instead of `SERVER_ADDR`.
+//!
+//! This is intentionally small: one toroidal `world` grid, one tick
+//! timer, collisions enforced as each tick moves the players. It exists for integration
+//! testing of client logic, not as a competitor to the real server.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Sender};
+use std::thread;
+use std::time::Duration;
+
+use crate::protocol::Message;
+use crate::PlayerID;
+
+const DEFAULT_WIDTH: usize = 20;
+const DEFAULT_HEIGHT: usize = 20;
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Heading {
+    Right,
+    Left,
+    Up,
+    Down,
+}
+
+impl Heading {
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Heading::Right => (1, 0),
+            Heading::Left => (-1, 0),
+            Heading::Up => (0, 1),
+            Heading::Down => (0, -1),
+        }
+    }
+}
+
+struct Player {
+    name: String,
+    pos: (usize, usize),
+    heading: Heading,
+    alive: bool,
+    wins: u32,
+    losses: u32,
+    stream: TcpStream,
+}
+
+struct World {
+    width: usize,
+    height: usize,
+    claimed: Vec<Vec<Option<PlayerID>>>,
+}
+
+impl World {
+    fn new(width: usize, height: usize) -> Self {
+        World {
+            width,
+            height,
+            claimed: vec![vec![None; height]; width],
+        }
+    }
+
+    fn claim(&mut self, id: PlayerID, x: usize, y: usize) {
+        self.claimed[x][y] = Some(id);
+    }
+
+    fn is_claimed(&self, x: usize, y: usize) -> bool {
+        self.claimed[x][y].is_some()
+    }
+
+    fn free(&mut self, id: PlayerID) {
+        for column in self.claimed.iter_mut() {
+            for cell in column.iter_mut() {
+                if *cell == Some(id) {
+                    *cell = None;
+                }
+            }
+        }
+    }
+}
+
+struct Match {
+    world: World,
+    players: HashMap<PlayerID, Player>,
+    next_id: PlayerID,
+}
+
+impl Match {
+    fn new(width: usize, height: usize) -> Self {
+        Match {
+            world: World::new(width, height),
+            players: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn broadcast(&mut self, msg: &str) {
+        for player in self.players.values_mut() {
+            let _ = player.stream.write_all(msg.as_bytes());
+            let _ = player.stream.flush();
+        }
+    }
+
+    fn send_to(&mut self, id: PlayerID, msg: &str) {
+        if let Some(player) = self.players.get_mut(&id) {
+            let _ = player.stream.write_all(msg.as_bytes());
+            let _ = player.stream.flush();
+        }
+    }
+
+    fn join(&mut self, name: String, stream: TcpStream) -> PlayerID {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        // spawn at a pseudo-random-ish cell, deterministic enough for testing,
+        // but skip forward past any cell that's already claimed - with a
+        // long-running Match and auto-rejoin cycling `next_id` forever, the
+        // raw formula alone will eventually collide with a live player
+        let start_x = (id * 3 + 1) % self.world.width;
+        let start_y = (id * 5 + 2) % self.world.height;
+        let (x, y) = (0..self.world.width * self.world.height)
+            .map(|offset| {
+                let cell = start_x * self.world.height + start_y + offset;
+                (
+                    (cell / self.world.height) % self.world.width,
+                    cell % self.world.height,
+                )
+            })
+            .find(|&(cx, cy)| !self.world.is_claimed(cx, cy))
+            .unwrap_or((start_x, start_y));
+        self.world.claim(id, x, y);
+
+        self.players.insert(
+            id,
+            Player {
+                name: name.clone(),
+                pos: (x, y),
+                heading: Heading::Right,
+                alive: true,
+                wins: 0,
+                losses: 0,
+                stream,
+            },
+        );
+
+        // `game` carries the joiner's own player ID, which main.rs's
+        // `Message::Game` handler uses to set `self.me` - sending it as a
+        // broadcast would reset every other connected client's identity too
+        self.send_to(id, &Message::encode("game", &[
+            &self.world.width.to_string(),
+            &self.world.height.to_string(),
+            &id.to_string(),
+        ]));
+        self.broadcast(&Message::encode("player", &[&id.to_string(), &name]));
+        self.broadcast(&Message::encode("pos", &[&id.to_string(), &x.to_string(), &y.to_string()]));
+
+        id
+    }
+
+    fn set_heading(&mut self, id: PlayerID, heading: Heading) {
+        if let Some(player) = self.players.get_mut(&id) {
+            player.heading = heading;
+        }
+    }
+
+    fn chat(&mut self, id: PlayerID, text: &str) {
+        self.broadcast(&Message::encode("chat", &[&id.to_string(), text]));
+    }
+
+    fn tick(&mut self) {
+        let ids: Vec<PlayerID> = self
+            .players
+            .iter()
+            .filter(|(_, p)| p.alive)
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in ids {
+            let (dx, dy) = self.players[&id].heading.delta();
+            let (x, y) = self.players[&id].pos;
+            let nx = ((x as isize + dx + self.world.width as isize) as usize) % self.world.width;
+            let ny = ((y as isize + dy + self.world.height as isize) as usize) % self.world.height;
+
+            if self.world.is_claimed(nx, ny) {
+                self.kill(id);
+                continue;
+            }
+
+            self.world.claim(id, nx, ny);
+            if let Some(player) = self.players.get_mut(&id) {
+                player.pos = (nx, ny);
+            }
+            self.broadcast(&Message::encode("pos", &[&id.to_string(), &nx.to_string(), &ny.to_string()]));
+        }
+
+        self.broadcast(&Message::encode("tick", &[]));
+        self.check_for_winner();
+    }
+
+    fn kill(&mut self, id: PlayerID) {
+        if let Some(player) = self.players.get_mut(&id) {
+            player.alive = false;
+            println!("Player {} (\"{}\") died", id, player.name);
+        }
+        self.world.free(id);
+        self.broadcast(&Message::encode("die", &[&id.to_string()]));
+
+        if let Some(player) = self.players.get_mut(&id) {
+            player.losses += 1;
+        }
+        let (wins, losses) = self
+            .players
+            .get(&id)
+            .map(|p| (p.wins, p.losses))
+            .unwrap_or((0, 0));
+        self.send_to(id, &Message::encode("lose", &[&wins.to_string(), &losses.to_string()]));
+    }
+
+    fn check_for_winner(&mut self) {
+        let alive: Vec<PlayerID> = self
+            .players
+            .iter()
+            .filter(|(_, p)| p.alive)
+            .map(|(id, _)| *id)
+            .collect();
+        if alive.len() == 1 && self.players.len() > 1 {
+            let winner = alive[0];
+            if let Some(player) = self.players.get_mut(&winner) {
+                player.wins += 1;
+            }
+            self.send_to(winner, &Message::encode("win", &[]));
+        }
+    }
+}
+
+enum ClientCommand {
+    Join { name: String },
+    Move(Heading),
+    Chat(String),
+}
+
+fn parse_client_command(line: &str) -> Option<ClientCommand> {
+    let line = line.trim();
+    let mut parts = line.split('|');
+    match parts.next()? {
+        // the reference server doesn't check the password; it exists in
+        // the wire format purely for compatibility with real clients
+        "join" => Some(ClientCommand::Join {
+            name: parts.next()?.to_string(),
+        }),
+        "move" => {
+            let dir = match parts.next()? {
+                "right" => Heading::Right,
+                "left" => Heading::Left,
+                "up" => Heading::Up,
+                "down" => Heading::Down,
+                _ => return None,
+            };
+            Some(ClientCommand::Move(dir))
+        }
+        "chat" => Some(ClientCommand::Chat(parts.next()?.to_string())),
+        _ => None,
+    }
+}
+
+enum ServerEvent {
+    Join { name: String, stream: TcpStream, id_tx: Sender<PlayerID> },
+    Move { id: PlayerID, heading: Heading },
+    Chat { id: PlayerID, text: String },
+}
+
+/// Runs the reference server, blocking forever.
+///
+/// Spawns one thread per connection to read that client's commands (the
+/// bare minimum needed before we know its `PlayerID`), and a separate
+/// ticker thread that advances the match on a timer. Both funnel events
+/// through a channel into a single thread owning the `Match`, so the
+/// authoritative state is never touched concurrently.
+pub fn run(addr: &str) {
+    println!("Starting local reference server on {}", addr);
+    let listener = TcpListener::bind(addr).expect("Cannot bind server address");
+
+    let (event_tx, event_rx) = channel::<ServerEvent>();
+
+    // accept loop: one thread per connection
+    {
+        let event_tx = event_tx.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(s) => s,
+                    Err(_) => continue,
+                };
+                let event_tx = event_tx.clone();
+                thread::spawn(move || handle_connection(stream, event_tx));
+            }
+        });
+    }
+
+    // ticker thread
+    {
+        let event_tx = event_tx.clone();
+        thread::spawn(move || loop {
+            thread::sleep(TICK_INTERVAL);
+            // an empty chat event is used as a tick heartbeat;
+            // the owning thread below drives the real Match::tick()
+            if event_tx.send(ServerEvent::Chat { id: 0, text: String::new() }).is_err() {
+                return;
+            }
+        });
+    }
+
+    run_match_loop(event_rx);
+}
+
+fn run_match_loop(event_rx: std::sync::mpsc::Receiver<ServerEvent>) {
+    let mut m = Match::new(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+    let mut last_tick = std::time::Instant::now();
+
+    loop {
+        let event = match event_rx.recv() {
+            Ok(e) => e,
+            Err(_) => return,
+        };
+
+        match event {
+            ServerEvent::Join { name, stream, id_tx } => {
+                let id = m.join(name, stream);
+                let _ = id_tx.send(id);
+            }
+            ServerEvent::Move { id, heading } => m.set_heading(id, heading),
+            ServerEvent::Chat { id, text } => {
+                if !text.is_empty() {
+                    m.chat(id, &text);
+                }
+            }
+        }
+
+        if last_tick.elapsed() >= TICK_INTERVAL {
+            m.tick();
+            last_tick = std::time::Instant::now();
+        }
+    }
+}
+
+fn handle_connection(stream: TcpStream, event_tx: Sender<ServerEvent>) {
+    let mut reader = BufReader::new(stream.try_clone().expect("Cannot clone TcpStream"));
+    let mut my_id: Option<PlayerID> = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        match reader.read_line(&mut line) {
+            Ok(0) | Err(_) => return,
+            Ok(_) => {}
+        }
+
+        match parse_client_command(&line) {
+            Some(ClientCommand::Join { name }) => {
+                let conn = stream.try_clone().expect("Cannot clone TcpStream");
+                let (id_tx, id_rx) = channel();
+                if event_tx.send(ServerEvent::Join { name, stream: conn, id_tx }).is_err() {
+                    return;
+                }
+                my_id = id_rx.recv().ok();
+            }
+            Some(ClientCommand::Move(heading)) => {
+                let sent_ok = my_id.map(|id| event_tx.send(ServerEvent::Move { id, heading }).is_ok());
+                if sent_ok == Some(false) {
+                    return;
+                }
+            }
+            Some(ClientCommand::Chat(text)) => {
+                let sent_ok = my_id.map(|id| event_tx.send(ServerEvent::Chat { id, text }).is_ok());
+                if sent_ok == Some(false) {
+                    return;
+                }
+            }
+            None => { /* ignore malformed lines, same tolerance as the real server */ }
+        }
+    }
+}
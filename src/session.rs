@@ -0,0 +1,90 @@
+//! Tracks outcomes across multiple matches and drives auto-rejoin, so
+//! unattended runs keep queueing for the next match instead of idling
+//! after a `win`/`lose`, and leave behind a usable evaluation log.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+const DEFAULT_STATS_FILE: &str = "./stats.log";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MatchResult {
+    Won,
+    Lost,
+}
+
+pub(crate) struct GameOutcome {
+    pub(crate) result: MatchResult,
+    pub(crate) claimed_cells: usize,
+    pub(crate) surviving_opponents: usize,
+    pub(crate) ticks: u32,
+    pub(crate) timestamp_secs: u64,
+}
+
+pub(crate) struct Session {
+    stats_path: PathBuf,
+    outcomes: Vec<GameOutcome>,
+    match_cap: Option<u32>,
+}
+
+impl Session {
+    pub(crate) fn new(stats_path: Option<String>, match_cap: Option<u32>) -> Self {
+        Session {
+            stats_path: PathBuf::from(stats_path.unwrap_or(String::from(DEFAULT_STATS_FILE))),
+            outcomes: Vec::new(),
+            match_cap,
+        }
+    }
+
+    /// Appends `outcome` to the local stats file and remembers it for
+    /// the exit summary.
+    pub(crate) fn record(&mut self, outcome: GameOutcome) {
+        let line = format!(
+            "{}\t{}\t{}\t{}\t{}\n",
+            outcome.timestamp_secs,
+            if outcome.result == MatchResult::Won { "win" } else { "lose" },
+            outcome.claimed_cells,
+            outcome.surviving_opponents,
+            outcome.ticks,
+        );
+
+        match OpenOptions::new().create(true).append(true).open(&self.stats_path) {
+            Ok(mut file) => {
+                if let Err(e) = file.write_all(line.as_bytes()) {
+                    println!("Cannot write to stats file {:?}: {}", self.stats_path, e);
+                }
+            }
+            Err(e) => println!("Cannot open stats file {:?}: {}", self.stats_path, e),
+        }
+
+        self.outcomes.push(outcome);
+    }
+
+    /// Whether we should send another `join` after this match, i.e. we
+    /// haven't hit the configured match cap yet.
+    pub(crate) fn should_rejoin(&self) -> bool {
+        match self.match_cap {
+            None => true,
+            Some(cap) => (self.outcomes.len() as u32) < cap,
+        }
+    }
+
+    /// A human-readable summary of every match played this session,
+    /// printed on `quit`.
+    pub(crate) fn summary(&self) -> String {
+        if self.outcomes.is_empty() {
+            return String::from("No matches played this session.");
+        }
+
+        let total = self.outcomes.len();
+        let wins = self.outcomes.iter().filter(|o| o.result == MatchResult::Won).count();
+        let total_ticks: u64 = self.outcomes.iter().map(|o| o.ticks as u64).sum();
+        let avg_ticks = total_ticks as f64 / total as f64;
+
+        format!(
+            "Played {} match(es): {} won ({:.1}% win rate), average survival length {:.1} ticks",
+            total, wins, 100.0 * wins as f64 / total as f64, avg_ticks,
+        )
+    }
+}
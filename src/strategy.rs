@@ -0,0 +1,191 @@
+//! Move selection by simulated Voronoi territory control, replacing the
+//! old `beam` heuristic (longest clear straight line), which walked
+//! into dead ends and ignored opponents entirely.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Cell, Direction, PlayerID};
+
+const ALL_DIRECTIONS: [Direction; 4] =
+    [Direction::WPos, Direction::WNeg, Direction::HPos, Direction::HNeg];
+
+/// Steps one cell from `pos` in `dir`, wrapping on a torus of size
+/// `expanse` - the same `% expanse` arithmetic `beam` used.
+fn step(pos: (usize, usize), dir: Direction, expanse: usize) -> (usize, usize) {
+    match dir {
+        Direction::WPos => ((pos.0 + 1) % expanse, pos.1),
+        Direction::WNeg => ((pos.0 + expanse - 1) % expanse, pos.1),
+        Direction::HPos => (pos.0, (pos.1 + 1) % expanse),
+        Direction::HNeg => (pos.0, (pos.1 + expanse - 1) % expanse),
+    }
+}
+
+/// Floods out from `start` over unclaimed cells on the wrapped grid,
+/// returning the number of cells reachable (including `start`).
+pub(crate) fn flood_fill_area(world: &[Vec<Cell>], start: (usize, usize)) -> usize {
+    let expanse = world.len();
+    if world[start.0][start.1].claimed() {
+        return 0;
+    }
+
+    let mut seen = vec![vec![false; expanse]; expanse];
+    let mut queue = VecDeque::new();
+    seen[start.0][start.1] = true;
+    queue.push_back(start);
+
+    let mut count = 0;
+    while let Some(pos) = queue.pop_front() {
+        count += 1;
+        for dir in ALL_DIRECTIONS {
+            let next = step(pos, dir, expanse);
+            if !seen[next.0][next.1] && !world[next.0][next.1].claimed() {
+                seen[next.0][next.1] = true;
+                queue.push_back(next);
+            }
+        }
+    }
+    count
+}
+
+/// Runs a simultaneous multi-source BFS seeded by every `(player, head)`
+/// pair, expanding one wavefront layer at a time across unclaimed cells
+/// of `world`. A cell is owned by whichever player's wavefront reaches
+/// it first; cells reached by two or more players in the same layer go
+/// to no one. Returns the number of cells each player ends up owning.
+pub(crate) fn voronoi_counts(
+    world: &[Vec<Cell>],
+    seeds: &[(PlayerID, (usize, usize))],
+) -> HashMap<PlayerID, usize> {
+    let expanse = world.len();
+    // None = unvisited, Some(None) = contested, Some(Some(id)) = owned by id
+    let mut owner: Vec<Vec<Option<Option<PlayerID>>>> = vec![vec![None; expanse]; expanse];
+    // wavefront layer at which each cell was first reached
+    let mut dist: Vec<Vec<usize>> = vec![vec![usize::MAX; expanse]; expanse];
+    let mut frontier: VecDeque<(usize, usize, PlayerID)> = VecDeque::new();
+
+    for &(id, pos) in seeds {
+        if owner[pos.0][pos.1].is_some() {
+            continue;
+        }
+        owner[pos.0][pos.1] = Some(Some(id));
+        dist[pos.0][pos.1] = 0;
+        frontier.push_back((pos.0, pos.1, id));
+    }
+
+    let mut layer = 0;
+    while !frontier.is_empty() {
+        layer += 1;
+        // process a whole layer before letting any wavefront expand
+        // further, so same-distance ties are detected as contested
+        let layer_len = frontier.len();
+        for _ in 0..layer_len {
+            let (x, y, id) = frontier.pop_front().unwrap();
+            for dir in ALL_DIRECTIONS {
+                let (nx, ny) = step((x, y), dir, expanse);
+                if world[nx][ny].claimed() {
+                    continue;
+                }
+                match owner[nx][ny] {
+                    None => {
+                        owner[nx][ny] = Some(Some(id));
+                        dist[nx][ny] = layer;
+                        frontier.push_back((nx, ny, id));
+                    }
+                    Some(Some(other)) if other != id && dist[nx][ny] == layer => {
+                        owner[nx][ny] = Some(None)
+                    }
+                    _ => { /* already ours, already contested, or owned from an earlier layer */ }
+                }
+            }
+        }
+    }
+
+    let mut counts = HashMap::new();
+    for row in &owner {
+        for cell in row {
+            if let Some(Some(id)) = cell {
+                *counts.entry(*id).or_insert(0) += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// Picks the best legal move from `pos` for player `me`.
+///
+/// For each neighbor cell that isn't already claimed, scores it by the
+/// size of our Voronoi partition after moving there (seeded by our new
+/// head and every entry in `opponents`), with no opponents known we
+/// fall back to pure flood-fill space-filling. As a tie-breaker and
+/// anti-self-trap guard, a move whose own reachable area is smaller
+/// than `body_length` is heavily penalized.
+pub(crate) fn choose_move(
+    world: &[Vec<Cell>],
+    me: PlayerID,
+    pos: (usize, usize),
+    opponents: &[(PlayerID, (usize, usize))],
+    body_length: usize,
+) -> Option<Direction> {
+    let expanse = world.len();
+    let mut best: Option<(Direction, i64, usize)> = None;
+
+    for dir in ALL_DIRECTIONS {
+        let candidate = step(pos, dir, expanse);
+        if world[candidate.0][candidate.1].claimed() {
+            continue;
+        }
+
+        let flood_area = flood_fill_area(world, candidate);
+
+        let territory = if opponents.is_empty() {
+            flood_area as i64
+        } else {
+            let mut seeds: Vec<(PlayerID, (usize, usize))> = opponents.to_vec();
+            seeds.push((me, candidate));
+            let counts = voronoi_counts(world, &seeds);
+            *counts.get(&me).unwrap_or(&0) as i64
+        };
+
+        let self_trap_penalty = if flood_area < body_length { -1_000_000 } else { 0 };
+        let score = territory + self_trap_penalty;
+
+        let is_better = match best {
+            None => true,
+            Some((_, best_score, best_flood)) => {
+                score > best_score || (score == best_score && flood_area > best_flood)
+            }
+        };
+        if is_better {
+            best = Some((dir, score, flood_area));
+        }
+    }
+
+    best.map(|(dir, _, _)| dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Two heads on opposite sides of a symmetric square arena must
+    /// split the board roughly evenly - if opponent seeds get dropped
+    /// (e.g. by rejecting an already-claimed seed cell), one side ends
+    /// up with the whole board and the other with nothing.
+    #[test]
+    fn voronoi_counts_splits_symmetric_arena() {
+        let expanse = 10;
+        let mut world = vec![vec![Cell::new(); expanse]; expanse];
+        world[2][5] = Cell { claimed_by: Some(0) };
+        world[7][5] = Cell { claimed_by: Some(1) };
+
+        let counts = voronoi_counts(&world, &[(0, (2, 5)), (1, (7, 5))]);
+
+        let mine = *counts.get(&0).unwrap();
+        let theirs = *counts.get(&1).unwrap();
+        assert!(mine > 1 && theirs > 1, "expected a real split, got {mine} vs {theirs}");
+        assert!(
+            (mine as i64 - theirs as i64).abs() <= 2,
+            "expected a roughly even split, got {mine} vs {theirs}"
+        );
+    }
+}